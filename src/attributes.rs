@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::directory::glob_matches;
+
+/// How a path's contents are treated by the content-addressed store.
+///
+/// Text files have their line endings normalized to LF before hashing, so the
+/// same source hashes to the same [`ObjectId`](crate::object_id::ObjectId) on
+/// Windows and Unix. Binary files are stored byte-for-byte.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TextMode {
+    Text,
+    Binary,
+    /// Detect text vs binary from the contents (NUL bytes imply binary).
+    Auto,
+}
+
+/// The original line-ending style of a file, recorded so each checkout can be
+/// restored to its native endings.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Eol {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+/// A set of path attributes, read from `.revattributes` files mapping glob
+/// patterns to `text`, `binary`, or `text=auto`.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Attributes {
+    rules: Vec<Rule>,
+}
+
+/// A single attribute rule, remembering the subtree it applies to so nested
+/// `.revattributes` inherit the same way ignore rules do (see
+/// [`Rule`](crate::directory)).
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Rule {
+    /// The directory, relative to the walk root, the pattern was declared in.
+    base: PathBuf,
+    pattern: String,
+    mode: TextMode,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Attributes::default()
+    }
+
+    /// Extend with the rules found in a `.revattributes` file whose directory
+    /// is `base` relative to the walk root. Blank lines and `#` comments are
+    /// skipped.
+    pub fn extend_from_file(&mut self, base: &Path, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            let mode = match parts.next() {
+                Some("text") => TextMode::Text,
+                Some("binary") => TextMode::Binary,
+                Some("text=auto") => TextMode::Auto,
+                _ => continue,
+            };
+            // Scope the rule to the directory the attributes file lives in by
+            // carrying `base` separately, so an unanchored pattern like
+            // `*.txt` keeps matching at any depth beneath `base`.
+            self.rules.push(Rule {
+                base: base.to_path_buf(),
+                pattern: pattern.to_string(),
+                mode,
+            });
+        }
+    }
+
+    /// The effective [`TextMode`] for a path, last matching rule winning.
+    pub fn mode_for(&self, relative_path: &Path) -> TextMode {
+        let mut mode = TextMode::Auto;
+        for rule in &self.rules {
+            if let Ok(sub) = relative_path.strip_prefix(&rule.base) {
+                if glob_matches(&rule.pattern, sub) {
+                    mode = rule.mode;
+                }
+            }
+        }
+        mode
+    }
+
+    /// Normalize a file's contents for storage. Text files have CRLF rewritten
+    /// to LF and their original style reported so it can be restored on
+    /// checkout; binary files are returned unchanged.
+    pub fn normalize(&self, relative_path: &Path, contents: &[u8]) -> (Vec<u8>, Eol) {
+        let is_text = match self.mode_for(relative_path) {
+            TextMode::Text => true,
+            TextMode::Binary => false,
+            TextMode::Auto => !looks_binary(contents),
+        };
+        if !is_text {
+            return (contents.to_vec(), Eol::Lf);
+        }
+        normalize_text(contents)
+    }
+}
+
+/// Rewrite CRLF to LF, reporting the original style. A file containing any
+/// `\r\n` is recorded as [`Eol::Crlf`] so the whole checkout is restored with
+/// CRLF endings.
+fn normalize_text(contents: &[u8]) -> (Vec<u8>, Eol) {
+    let mut out = Vec::with_capacity(contents.len());
+    let mut eol = Eol::Lf;
+    let mut i = 0;
+    while i < contents.len() {
+        if contents[i] == b'\r' && contents.get(i + 1) == Some(&b'\n') {
+            out.push(b'\n');
+            eol = Eol::Crlf;
+            i += 2;
+        } else {
+            out.push(contents[i]);
+            i += 1;
+        }
+    }
+    (out, eol)
+}
+
+/// Reapply a recorded line-ending style to normalized (LF) contents.
+pub fn reapply_eol(contents: &[u8], eol: Eol) -> Vec<u8> {
+    match eol {
+        Eol::Lf => contents.to_vec(),
+        Eol::Crlf => {
+            let mut out = Vec::with_capacity(contents.len());
+            for &b in contents {
+                if b == b'\n' {
+                    out.push(b'\r');
+                }
+                out.push(b);
+            }
+            out
+        }
+    }
+}
+
+/// A file is treated as binary if a NUL byte appears in the first few KB.
+fn looks_binary(contents: &[u8]) -> bool {
+    const SNIFF: usize = 8192;
+    contents[..contents.len().min(SNIFF)].contains(&0)
+}
+
+#[test]
+fn test_crlf_normalization_roundtrip() {
+    let attributes = Attributes::new();
+    let (normalized, eol) = attributes.normalize(Path::new("a.txt"), b"one\r\ntwo\r\n");
+    assert_eq!(normalized, b"one\ntwo\n");
+    assert_eq!(eol, Eol::Crlf);
+    assert_eq!(reapply_eol(&normalized, eol), b"one\r\ntwo\r\n");
+}
+
+#[test]
+fn test_auto_detects_binary() {
+    let attributes = Attributes::new();
+    let data = b"\x00\x01\r\n";
+    let (normalized, eol) = attributes.normalize(Path::new("blob.bin"), data);
+    // Binary data is stored verbatim, CRLF and all.
+    assert_eq!(normalized, data);
+    assert_eq!(eol, Eol::Lf);
+}
+
+#[test]
+fn test_attributes_override_auto() {
+    let mut attributes = Attributes::new();
+    attributes.extend_from_file(Path::new(""), "*.bin binary\n*.txt text\n");
+    assert_eq!(attributes.mode_for(Path::new("x.bin")), TextMode::Binary);
+    assert_eq!(attributes.mode_for(Path::new("x.txt")), TextMode::Text);
+    assert_eq!(attributes.mode_for(Path::new("x.rs")), TextMode::Auto);
+}
+
+#[test]
+fn test_nested_attributes_match_at_any_depth() {
+    // An unanchored pattern from a nested `.revattributes` keeps matching
+    // deeper paths under its directory, not just immediate children.
+    let mut attributes = Attributes::new();
+    attributes.extend_from_file(Path::new("sub"), "*.txt text\n");
+    assert_eq!(attributes.mode_for(Path::new("sub/a.txt")), TextMode::Text);
+    assert_eq!(
+        attributes.mode_for(Path::new("sub/deep/a.txt")),
+        TextMode::Text
+    );
+    // Outside the subtree it does not apply.
+    assert_eq!(attributes.mode_for(Path::new("a.txt")), TextMode::Auto);
+}
@@ -1,13 +1,17 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
-    fs::{read_dir, File},
-    io::{Read, Write},
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::{object_id::ObjectId, object_store::ObjectStore};
+use crate::{
+    attributes::{reapply_eol, Attributes, Eol},
+    fs::Fs,
+    object_id::ObjectId,
+    object_store::ObjectStore,
+};
 
 /// A directory tree, with [`ObjectId`]s at the leaves.
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, Default)]
@@ -32,7 +36,8 @@ pub struct Diff {
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub enum DiffEntry {
-    File(ObjectId),
+    File { id: ObjectId, executable: bool },
+    Symlink(String),
     Directory(Box<Diff>),
 }
 
@@ -40,19 +45,37 @@ impl DirectoryEntry {
     pub fn diff(&self, other: &DirectoryEntry) -> Option<DiffEntry> {
         use DirectoryEntry::*;
         match (self, other) {
-            (File(id), File(id_)) => {
-                if id != id_ {
-                    Some(DiffEntry::File(*id_))
+            // Same kind: a change in contents, mode, or link target is a diff.
+            // Line-ending style is normalization metadata, not a content
+            // change, so it is deliberately ignored here.
+            (
+                File {
+                    id,
+                    executable,
+                    eol: _,
+                },
+                File {
+                    id: id_,
+                    executable: executable_,
+                    eol: _,
+                },
+            ) => {
+                if id != id_ || executable != executable_ {
+                    Some(DiffEntry::File {
+                        id: *id_,
+                        executable: *executable_,
+                    })
+                } else {
+                    None
+                }
+            }
+            (Symlink(target), Symlink(target_)) => {
+                if target != target_ {
+                    Some(DiffEntry::Symlink(target_.clone()))
                 } else {
                     None
                 }
             }
-            (Directory(_), File(id)) => Some(DiffEntry::File(*id)),
-            (File(_), Directory(d)) => Some(DiffEntry::Directory(Box::new(Diff {
-                deleted: BTreeSet::new(),
-                added: d.root.clone(),
-                modified: BTreeMap::new(),
-            }))),
             (Directory(d), Directory(d_)) => {
                 if d == d_ {
                     None
@@ -60,6 +83,24 @@ impl DirectoryEntry {
                     Some(DiffEntry::Directory(Box::new(d.diff(d_))))
                 }
             }
+            // Kind changed: report the new entry.
+            (
+                _,
+                File {
+                    id,
+                    executable,
+                    eol: _,
+                },
+            ) => Some(DiffEntry::File {
+                id: *id,
+                executable: *executable,
+            }),
+            (_, Symlink(target)) => Some(DiffEntry::Symlink(target.clone())),
+            (_, Directory(d)) => Some(DiffEntry::Directory(Box::new(Diff {
+                deleted: BTreeSet::new(),
+                added: d.root.clone(),
+                modified: BTreeMap::new(),
+            }))),
         }
     }
 }
@@ -103,28 +144,44 @@ impl Directory {
     /// The target directory must already exist.
     pub fn write<Store: ObjectStore>(
         &self,
+        fs: &dyn Fs,
         store: &Store,
         path: &Path,
     ) -> Result<(), Error<Store>> {
-        if read_dir(path).is_ok() {
+        if fs.metadata(path).map(|m| m.is_dir()).unwrap_or(false) {
             for (file_name, entry) in self.root.iter() {
                 match entry {
-                    DirectoryEntry::File(id) => {
+                    DirectoryEntry::File {
+                        id,
+                        executable,
+                        eol,
+                    } => {
                         let v = store.read(*id).map_err(Error::Store)?;
                         match v {
                             Some(v) => {
-                                let mut f = File::options()
-                                    .create(true)
-                                    .write(true)
-                                    .open(path.join(file_name))
+                                let file_path = path.join(file_name);
+                                // Restore the checkout's native line endings.
+                                let v = reapply_eol(&v, *eol);
+                                fs.write_file(&file_path, &v).map_err(Error::IO)?;
+                                fs.set_executable(&file_path, *executable)
                                     .map_err(Error::IO)?;
-                                f.write(&v).map_err(Error::IO)?;
                             }
                             None => return Err(Error::ObjectMissing(*id)),
                         }
                     }
+                    DirectoryEntry::Symlink(target) => {
+                        // `symlink(2)` fails with `EEXIST` if the destination
+                        // exists, so clear any prior entry first to keep
+                        // checkout idempotent like the file arm.
+                        let link_path = path.join(file_name);
+                        remove_existing(fs, &link_path).map_err(Error::IO)?;
+                        fs.write_symlink(Path::new(target), &link_path)
+                            .map_err(Error::IO)?;
+                    }
                     DirectoryEntry::Directory(dir) => {
-                        dir.write(store, PathBuf::from(path).join(file_name).as_path())?;
+                        let child = path.join(file_name);
+                        fs.create_dir(&child).map_err(Error::IO)?;
+                        dir.write(fs, store, child.as_path())?;
                     }
                 }
             }
@@ -133,69 +190,419 @@ impl Directory {
     }
 }
 
-/// The set of file names which we will ignore at any level.
+/// A compiled set of ignore rules, applied in order with last-match-wins
+/// semantics.
+///
+/// Patterns follow the usual gitignore-style conventions: a leading `/`
+/// anchors the pattern to the directory it was declared in, a trailing `/`
+/// restricts it to directories, `*`, `?` and `[...]` are glob wildcards, a
+/// `**` segment spans any number of path segments, and a leading `!`
+/// re-includes a path excluded by an earlier rule. Rules loaded from a
+/// nested `.revignore` are scoped to the subtree that contains them.
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct Ignores {
-    pub set: BTreeSet<String>,
+    rules: Vec<Rule>,
+}
+
+/// A single compiled ignore rule, remembering the subtree it applies to.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+struct Rule {
+    /// The directory, relative to the walk root, the pattern was declared in.
+    base: PathBuf,
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// A glob compiled into path segments.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+struct Pattern {
+    segments: Vec<Seg>,
+    /// Whether the pattern is anchored to `base` rather than matching at any
+    /// depth beneath it.
+    anchored: bool,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+enum Seg {
+    /// A `**` segment, matching zero or more path segments.
+    DoubleStar,
+    /// A single path segment, as a sequence of glob tokens.
+    Literal(Vec<Token>),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+enum Token {
+    Char(char),
+    /// `?`
+    AnyChar,
+    /// `*`
+    AnyRun,
+    /// `[...]`
+    Class { negated: bool, ranges: Vec<(char, char)> },
 }
 
 impl Default for Ignores {
     fn default() -> Self {
-        Ignores {
-            set: vec![String::from(".rev")].into_iter().collect(),
+        let mut ignores = Ignores::default_empty();
+        ignores.push_pattern(Path::new(""), ".rev");
+        ignores
+    }
+}
+
+impl Ignores {
+    /// An ignore set with no rules at all.
+    pub fn default_empty() -> Self {
+        Ignores { rules: Vec::new() }
+    }
+
+    /// Compile a list of gitignore-style patterns rooted at the walk root.
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut ignores = Ignores::default_empty();
+        for pattern in patterns {
+            ignores.push_pattern(Path::new(""), pattern.as_ref());
+        }
+        ignores
+    }
+
+    /// Extend with the patterns found in a `.revignore` file whose directory
+    /// is `base` relative to the walk root. Blank lines and `#` comments are
+    /// skipped, so nested ignore files compose with the inherited rules.
+    pub fn extend_from_file(&mut self, base: &Path, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            self.push_pattern(base, line);
+        }
+    }
+
+    fn push_pattern(&mut self, base: &Path, raw: &str) {
+        let mut rest = raw;
+        let mut negated = false;
+        if let Some(stripped) = rest.strip_prefix('!') {
+            negated = true;
+            rest = stripped;
+        }
+        let mut dir_only = false;
+        if let Some(stripped) = rest.strip_suffix('/') {
+            dir_only = true;
+            rest = stripped;
+        }
+        // A leading slash anchors to `base`; an interior slash also anchors,
+        // matching gitignore's rule that only slash-free patterns float.
+        let anchored = rest.starts_with('/') || rest.trim_end_matches('/').contains('/');
+        let rest = rest.trim_start_matches('/');
+        let segments = rest
+            .split('/')
+            .map(|seg| {
+                if seg == "**" {
+                    Seg::DoubleStar
+                } else {
+                    Seg::Literal(compile_segment(seg))
+                }
+            })
+            .collect();
+        self.rules.push(Rule {
+            base: base.to_path_buf(),
+            pattern: Pattern { segments, anchored },
+            negated,
+            dir_only,
+        });
+    }
+
+    /// Whether `relative_path` (relative to the walk root) should be ignored,
+    /// applying the rules in order so that the last match wins.
+    pub fn matches(&self, relative_path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let Ok(sub) = relative_path.strip_prefix(&rule.base) else {
+                continue;
+            };
+            let segments: Vec<&str> = sub
+                .iter()
+                .map(|s| s.to_str().unwrap_or_default())
+                .collect();
+            if rule.pattern.matches(&segments) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+impl Pattern {
+    fn matches(&self, path: &[&str]) -> bool {
+        if self.anchored {
+            match_segments(&self.segments, path)
+        } else {
+            // Unanchored patterns may start at any depth.
+            (0..=path.len()).any(|start| match_segments(&self.segments, &path[start..]))
+        }
+    }
+}
+
+/// Match a sequence of pattern segments against path segments, treating
+/// [`Seg::DoubleStar`] as matching zero or more segments.
+fn match_segments(pattern: &[Seg], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((Seg::DoubleStar, rest)) if rest.is_empty() => {
+            // A trailing `**` (as in `foo/**`) matches the *contents* of the
+            // directory — one or more segments — not the directory itself.
+            !path.is_empty()
+        }
+        Some((Seg::DoubleStar, rest)) => {
+            // An interior `**` may span zero or more segments.
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((Seg::Literal(tokens), rest)) => match path.split_first() {
+            Some((head, tail)) if match_tokens(tokens, head) => match_segments(rest, tail),
+            _ => false,
+        },
+    }
+}
+
+/// Match glob tokens against a single path segment, backtracking over `*`.
+fn match_tokens(tokens: &[Token], seg: &str) -> bool {
+    let chars: Vec<char> = seg.chars().collect();
+    fn go(tokens: &[Token], chars: &[char]) -> bool {
+        match tokens.split_first() {
+            None => chars.is_empty(),
+            Some((Token::AnyRun, rest)) => {
+                (0..=chars.len()).any(|skip| go(rest, &chars[skip..]))
+            }
+            Some((Token::AnyChar, rest)) => !chars.is_empty() && go(rest, &chars[1..]),
+            Some((Token::Char(c), rest)) => {
+                !chars.is_empty() && chars[0] == *c && go(rest, &chars[1..])
+            }
+            Some((Token::Class { negated, ranges }, rest)) => {
+                if chars.is_empty() {
+                    return false;
+                }
+                let hit = ranges.iter().any(|(lo, hi)| chars[0] >= *lo && chars[0] <= *hi);
+                (hit != *negated) && go(rest, &chars[1..])
+            }
+        }
+    }
+    go(tokens, &chars)
+}
+
+/// Test a single gitignore-style glob pattern against a path, reusing the
+/// ignore matcher. Used by the attributes subsystem.
+pub(crate) fn glob_matches(pattern: &str, relative_path: &Path) -> bool {
+    Ignores::new([pattern]).matches(relative_path, false)
+}
+
+/// Remove a file or symlink at `path` if one exists, treating a missing path
+/// as success. Used to make symlink checkout idempotent.
+fn remove_existing(fs: &dyn Fs, path: &Path) -> std::io::Result<()> {
+    match fs.remove(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        other => other,
+    }
+}
+
+/// Compile a single glob segment into tokens.
+fn compile_segment(seg: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = seg.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => tokens.push(Token::AnyRun),
+            '?' => tokens.push(Token::AnyChar),
+            '[' => {
+                let mut negated = false;
+                if matches!(chars.peek(), Some('!' | '^')) {
+                    negated = true;
+                    chars.next();
+                }
+                let mut ranges = Vec::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ']' {
+                        chars.next();
+                        break;
+                    }
+                    chars.next();
+                    if matches!(chars.peek(), Some('-')) {
+                        chars.next();
+                        if let Some(&hi) = chars.peek() {
+                            if hi != ']' {
+                                chars.next();
+                                ranges.push((c, hi));
+                                continue;
+                            }
+                        }
+                        ranges.push((c, c));
+                        ranges.push(('-', '-'));
+                    } else {
+                        ranges.push((c, c));
+                    }
+                }
+                tokens.push(Token::Class { negated, ranges });
+            }
+            c => tokens.push(Token::Char(c)),
         }
     }
+    tokens
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub enum DirectoryEntry {
     Directory(Box<Directory>),
-    File(ObjectId),
+    File {
+        id: ObjectId,
+        executable: bool,
+        /// The original line-ending style, reapplied on checkout.
+        eol: Eol,
+    },
+    /// A symbolic link, storing its target path rather than any contents.
+    Symlink(String),
+}
+
+/// A hook consulted by [`Directory::build`] so a stat cache (the dirstate) can
+/// avoid re-reading and re-hashing files that have not changed, without the
+/// walk itself being duplicated. The walk is the single source of truth for
+/// how a file is normalized into an [`ObjectId`].
+pub trait WalkCache {
+    /// Return the cached `(id, eol)` for `relative` if its freshly stat'd
+    /// `len`/`modified` prove it unchanged, otherwise `None`.
+    fn reuse(
+        &self,
+        relative: &Path,
+        len: u64,
+        modified: Option<SystemTime>,
+    ) -> Option<(ObjectId, Eol)>;
+
+    /// Record the outcome computed for `relative` by the walk.
+    fn record(
+        &mut self,
+        relative: &Path,
+        len: u64,
+        modified: Option<SystemTime>,
+        id: ObjectId,
+        eol: Eol,
+    );
 }
 
 impl Directory {
     pub fn new<Store: ObjectStore>(
+        fs: &dyn Fs,
+        dir: &Path,
+        ignores: &Ignores,
+        attributes: &Attributes,
+        store: &mut Store,
+    ) -> Result<Self, Error<Store>> {
+        Self::build(fs, dir, ignores, attributes, store, None)
+    }
+
+    /// Walk `dir`, hashing every file into `store`, optionally consulting a
+    /// [`WalkCache`] to skip unchanged files. This is the only walk: the
+    /// dirstate layers caching on top of it via the hook rather than carrying
+    /// its own copy.
+    pub fn build<Store: ObjectStore>(
+        fs: &dyn Fs,
         dir: &Path,
         ignores: &Ignores,
+        attributes: &Attributes,
         store: &mut Store,
+        mut cache: Option<&mut dyn WalkCache>,
     ) -> Result<Self, Error<Store>> {
-        let mut root = BTreeMap::new();
-        for f in std::fs::read_dir(dir).map_err(Error::IO)? {
-            let dir_entry = f.map_err(Error::IO)?;
-            if ignores
-                .set
-                .contains(&dir_entry.file_name().into_string().unwrap())
-            {
+        Self::walk(fs, dir, dir, ignores, attributes, store, cache.as_deref_mut())
+    }
+
+    /// Walk `dir`, resolving ignore rules against paths relative to `root` so
+    /// that anchored patterns and nested `.revignore` files compose correctly.
+    fn walk<Store: ObjectStore>(
+        fs: &dyn Fs,
+        root: &Path,
+        dir: &Path,
+        ignores: &Ignores,
+        attributes: &Attributes,
+        store: &mut Store,
+        mut cache: Option<&mut dyn WalkCache>,
+    ) -> Result<Self, Error<Store>> {
+        // Nested `.revignore` and `.revattributes` files extend the inherited
+        // rules for this subtree only.
+        let mut ignores = ignores.clone();
+        if let Ok(contents) = fs.read_file(&dir.join(".revignore")) {
+            if let Ok(contents) = String::from_utf8(contents) {
+                let base = dir.strip_prefix(root).unwrap_or(Path::new(""));
+                ignores.extend_from_file(base, &contents);
+            }
+        }
+        let mut attributes = attributes.clone();
+        if let Ok(contents) = fs.read_file(&dir.join(".revattributes")) {
+            if let Ok(contents) = String::from_utf8(contents) {
+                let base = dir.strip_prefix(root).unwrap_or(Path::new(""));
+                attributes.extend_from_file(base, &contents);
+            }
+        }
+        let mut root_map = BTreeMap::new();
+        for dir_entry in fs.read_dir(dir).map_err(Error::IO)? {
+            let path = &dir_entry.path;
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let metadata = &dir_entry.metadata;
+            if ignores.matches(relative, metadata.is_dir()) {
                 continue;
             }
-            let file_type = dir_entry.file_type().map_err(Error::IO)?;
-            if file_type.is_dir() {
-                let directory = Directory::new(dir_entry.path().as_path(), ignores, store)?;
-                root.insert(
-                    dir_entry.file_name().into_string().unwrap(),
+            if metadata.is_dir() {
+                let directory = Directory::walk(
+                    fs,
+                    root,
+                    path.as_path(),
+                    &ignores,
+                    &attributes,
+                    store,
+                    cache.as_deref_mut(),
+                )?;
+                root_map.insert(
+                    dir_entry.file_name.clone(),
                     DirectoryEntry::Directory(Box::new(directory)),
                 );
-            } else if file_type.is_file() {
-                let id = ObjectId::try_from(dir_entry.path().as_path()).map_err(Error::IO)?;
-                root.insert(
-                    dir_entry.file_name().into_string().unwrap(),
-                    DirectoryEntry::File(id),
+            } else if metadata.is_symlink() {
+                let target = fs.read_link(path).map_err(Error::IO)?;
+                root_map.insert(
+                    dir_entry.file_name.clone(),
+                    DirectoryEntry::Symlink(target.to_string_lossy().into_owned()),
                 );
-                let mut v = Vec::new();
-                let mut obj_file = File::options()
-                    .read(true)
-                    .open(dir_entry.path())
-                    .map_err(Error::IO)?;
-                obj_file.read_to_end(&mut v).map_err(Error::IO)?;
-                store.insert(&v).map_err(Error::Store)?;
-            } else {
-                eprintln!(
-                    "TODO support things which aren't files or directories: {:?}",
-                    dir_entry.file_name()
+            } else if metadata.is_file() {
+                let len = metadata.len;
+                let modified = metadata.modified;
+                let (id, eol) = match cache.as_ref().and_then(|c| c.reuse(relative, len, modified)) {
+                    Some(hit) => hit,
+                    None => {
+                        let raw = fs.read_file(path).map_err(Error::IO)?;
+                        // Normalize line endings so the object id is stable
+                        // across platforms; the original style is recorded for
+                        // checkout.
+                        let (normalized, eol) = attributes.normalize(relative, &raw);
+                        let id = store.insert(&normalized).map_err(Error::Store)?;
+                        (id, eol)
+                    }
+                };
+                if let Some(cache) = cache.as_deref_mut() {
+                    cache.record(relative, len, modified, id, eol);
+                }
+                root_map.insert(
+                    dir_entry.file_name.clone(),
+                    DirectoryEntry::File {
+                        id,
+                        executable: metadata.executable,
+                        eol,
+                    },
                 );
             }
         }
-        Ok(Directory { root })
+        Ok(Directory { root: root_map })
     }
 }
 
@@ -206,19 +613,86 @@ fn test_directory() {
     let dir = current_dir().unwrap();
     let mut store = InMemoryObjectStore::new();
     let codebase = Directory::new(
+        &crate::fs::RealFs,
         dir.as_path(),
-        &Ignores {
-            set: vec![
-                String::from(".git"),
-                String::from(".rev"),
-                String::from("target"),
-            ]
-            .into_iter()
-            .collect(),
-        },
+        &Ignores::new([".git", ".rev", "target"]),
+        &Attributes::new(),
         &mut store,
     )
     .unwrap();
     let readme_path = String::from("README.md");
     assert!(codebase.root.get(&readme_path).is_some());
 }
+
+#[test]
+fn test_ignores_glob() {
+    let ignores = Ignores::new(["*.o", "/build", "target/", "!keep.o"]);
+    assert!(ignores.matches(Path::new("main.o"), false));
+    assert!(ignores.matches(Path::new("src/util.o"), false));
+    assert!(!ignores.matches(Path::new("keep.o"), false));
+    assert!(ignores.matches(Path::new("build"), true));
+    // `/build` is anchored, so a nested `build` is not matched by it.
+    assert!(!ignores.matches(Path::new("src/build"), false));
+    // Trailing-slash patterns only match directories.
+    assert!(ignores.matches(Path::new("target"), true));
+    assert!(!ignores.matches(Path::new("target"), false));
+}
+
+#[test]
+fn test_double_star_excludes_only_contents() {
+    let ignores = Ignores::new(["foo/**"]);
+    // `foo/**` matches paths under `foo`, but not `foo` itself.
+    assert!(!ignores.matches(Path::new("foo"), true));
+    assert!(ignores.matches(Path::new("foo/a"), false));
+    assert!(ignores.matches(Path::new("foo/deep/a"), false));
+}
+
+#[test]
+fn test_directory_over_fake_fs() {
+    use crate::fs::FakeFs;
+    use crate::object_store::in_memory::InMemoryObjectStore;
+    let fs = FakeFs::new();
+    fs.insert_file("/repo/README.md", b"hello".to_vec());
+    fs.insert_file("/repo/src/main.rs", b"fn main() {}".to_vec());
+    fs.insert_file("/repo/target/junk", b"ignore me".to_vec());
+    let mut store = InMemoryObjectStore::new();
+    let codebase = Directory::new(
+        &fs,
+        Path::new("/repo"),
+        &Ignores::new(["target/"]),
+        &Attributes::new(),
+        &mut store,
+    )
+    .unwrap();
+    assert!(codebase.root.contains_key("README.md"));
+    assert!(codebase.root.contains_key("src"));
+    assert!(!codebase.root.contains_key("target"));
+}
+
+#[test]
+fn test_diff_reports_mode_and_symlink_changes() {
+    let id: ObjectId = (&b"contents"[..]).into();
+    // A mode-only change (same object id) is still a diff.
+    let plain = DirectoryEntry::File {
+        id,
+        executable: false,
+        eol: Eol::Lf,
+    };
+    let exec = DirectoryEntry::File {
+        id,
+        executable: true,
+        eol: Eol::Lf,
+    };
+    assert_eq!(
+        plain.diff(&exec),
+        Some(DiffEntry::File {
+            id,
+            executable: true
+        })
+    );
+    // A symlink whose target changed is a diff.
+    let a = DirectoryEntry::Symlink(String::from("a"));
+    let b = DirectoryEntry::Symlink(String::from("b"));
+    assert_eq!(a.diff(&b), Some(DiffEntry::Symlink(String::from("b"))));
+    assert_eq!(b.diff(&b), None);
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attributes::{Attributes, Eol},
+    directory::{Directory, Error, Ignores, WalkCache},
+    fs::Fs,
+    object_id::ObjectId,
+    object_store::ObjectStore,
+};
+
+/// A persistent cache of the stat data observed for each tracked file, used to
+/// skip re-reading and re-hashing files that have not changed since the last
+/// walk.
+///
+/// Entries record the file size, the modification time (whole seconds plus
+/// nanoseconds) and the
+/// [`ObjectId`] last computed for the contents. On [`Dirstate::refresh`] a file
+/// whose size and mtime still match its entry is taken to be unchanged and its
+/// cached id is reused without touching the contents.
+#[derive(PartialEq, Eq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Dirstate {
+    /// When the dirstate was last written. A file whose mtime is equal to or
+    /// newer than this instant is treated as ambiguous and always re-hashed,
+    /// so a same-second edit can never be mistaken for unchanged.
+    written_at: Timestamp,
+    entries: BTreeMap<PathBuf, Entry>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    size: u64,
+    mtime: Timestamp,
+    id: ObjectId,
+    eol: Eol,
+}
+
+/// A modification time recorded as whole seconds plus nanoseconds since the
+/// Unix epoch.
+///
+/// The full sub-second component is kept as the filesystem reports it; we do
+/// not rely on it being truncated. Correctness against coarse or sub-second
+/// resolution comes instead from the ambiguity check in the walk cache's
+/// `reuse` (see [`Dirstate::refresh`]): any
+/// file whose mtime is equal to or newer than [`Dirstate::written_at`] is
+/// re-hashed rather than trusted, so a same-second edit can never be mistaken
+/// for unchanged.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Timestamp {
+    secs: u64,
+    nanos: u32,
+}
+
+impl Timestamp {
+    fn from_system_time(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Timestamp {
+            secs: since_epoch.as_secs(),
+            nanos: since_epoch.subsec_nanos(),
+        }
+    }
+
+    fn now() -> Self {
+        Timestamp::from_system_time(SystemTime::now())
+    }
+}
+
+impl Dirstate {
+    /// Load a dirstate from `path`, returning the empty default when the file
+    /// does not yet exist.
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        match File::options().read(true).open(path) {
+            Ok(mut f) => {
+                let mut buf = String::new();
+                f.read_to_string(&mut buf)?;
+                serde_json::from_str(&buf)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Dirstate::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Serialize the dirstate to `path`.
+    pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
+        let buf = serde_json::to_vec(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, buf)
+    }
+
+    /// Walk `dir`, reusing cached object ids for files whose stat data is
+    /// unchanged and re-hashing the rest. Returns the up-to-date [`Directory`]
+    /// and the set of paths (relative to `dir`) whose contents changed, were
+    /// added, or were removed. The dirstate is updated in place; call
+    /// [`Dirstate::save`] afterwards to persist it.
+    pub fn refresh<Store: ObjectStore>(
+        &mut self,
+        fs: &dyn Fs,
+        dir: &Path,
+        ignores: &Ignores,
+        attributes: &Attributes,
+        store: &mut Store,
+    ) -> Result<(Directory, BTreeSet<PathBuf>), Error<Store>> {
+        let mut cache = DirstateCache {
+            old: &self.entries,
+            written_at: self.written_at,
+            next: BTreeMap::new(),
+            changed: BTreeSet::new(),
+        };
+        // Reuse the single walk in `directory`, layering caching on via the
+        // hook so there is no second, divergent walk to keep in sync.
+        let directory = Directory::build(fs, dir, ignores, attributes, store, Some(&mut cache))?;
+        let DirstateCache {
+            next, mut changed, ..
+        } = cache;
+        // Any path that was tracked before but is gone now counts as changed.
+        for path in self.entries.keys() {
+            if !next.contains_key(path) {
+                changed.insert(path.clone());
+            }
+        }
+        self.entries = next;
+        self.written_at = Timestamp::now();
+        Ok((directory, changed))
+    }
+}
+
+/// The dirstate's view while a walk is in progress: the previously recorded
+/// entries, plus the entries and changed paths being accumulated.
+struct DirstateCache<'a> {
+    old: &'a BTreeMap<PathBuf, Entry>,
+    written_at: Timestamp,
+    next: BTreeMap<PathBuf, Entry>,
+    changed: BTreeSet<PathBuf>,
+}
+
+impl WalkCache for DirstateCache<'_> {
+    fn reuse(
+        &self,
+        relative: &Path,
+        len: u64,
+        modified: Option<SystemTime>,
+    ) -> Option<(ObjectId, Eol)> {
+        // A backend without modification times (e.g. `FakeFs`) forces a
+        // re-hash every time.
+        let mtime = Timestamp::from_system_time(modified?);
+        let entry = self.old.get(relative)?;
+        // Reuse only when the stat data matches and the mtime predates the
+        // last write, so a same-second edit is never trusted.
+        (entry.size == len && entry.mtime == mtime && mtime < self.written_at)
+            .then_some((entry.id, entry.eol))
+    }
+
+    fn record(
+        &mut self,
+        relative: &Path,
+        len: u64,
+        modified: Option<SystemTime>,
+        id: ObjectId,
+        eol: Eol,
+    ) {
+        // Only report an actual content change: an ambiguous re-hash of an
+        // unmodified file yields the same id and must not appear as changed.
+        if self.old.get(relative).map(|e| e.id) != Some(id) {
+            self.changed.insert(relative.to_path_buf());
+        }
+        let mtime = modified.map(Timestamp::from_system_time).unwrap_or_default();
+        self.next.insert(
+            relative.to_path_buf(),
+            Entry {
+                size: len,
+                mtime,
+                id,
+                eol,
+            },
+        );
+    }
+}
@@ -0,0 +1,394 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+/// An abstraction over the filesystem operations the walk and checkout
+/// pipeline needs, so that it can run against the real local filesystem, an
+/// in-memory fake in tests, or (eventually) a remote backend.
+pub trait Fs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>>;
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>>;
+    /// Read the target of a symbolic link.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+    fn create_dir(&self, path: &Path) -> io::Result<()>;
+    fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    /// Create a symbolic link at `link` pointing at `target`.
+    fn write_symlink(&self, target: &Path, link: &Path) -> io::Result<()>;
+    /// Set or clear the executable bit on a regular file.
+    fn set_executable(&self, path: &Path, executable: bool) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn remove(&self, path: &Path) -> io::Result<()>;
+}
+
+/// An entry yielded by [`Fs::read_dir`], pairing the full path with its
+/// metadata so callers need not issue a separate `stat`.
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    pub file_type: FileType,
+    pub len: u64,
+    /// Whether the executable bit is set (always `false` for non-files and on
+    /// platforms without Unix permissions).
+    pub executable: bool,
+    /// Last modification time, when the backend can report one. Backends
+    /// without a notion of time (such as [`FakeFs`]) return `None`, which
+    /// callers must treat as "always changed".
+    pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    File,
+    Dir,
+    Symlink,
+}
+
+impl Metadata {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.file_type, FileType::Dir)
+    }
+
+    pub fn is_file(&self) -> bool {
+        matches!(self.file_type, FileType::File)
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        matches!(self.file_type, FileType::Symlink)
+    }
+}
+
+/// The production [`Fs`] implementation, delegating to [`std::fs`].
+pub struct RealFs;
+
+fn metadata_from_std(meta: &std::fs::Metadata) -> Metadata {
+    let file_type = if meta.is_dir() {
+        FileType::Dir
+    } else if meta.file_type().is_symlink() {
+        FileType::Symlink
+    } else {
+        FileType::File
+    };
+    Metadata {
+        file_type,
+        len: meta.len(),
+        executable: file_type == FileType::File && is_executable(meta),
+        modified: meta.modified().ok(),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
+
+impl Fs for RealFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            entries.push(DirEntry {
+                path: entry.path(),
+                file_name: entry.file_name().into_string().unwrap(),
+                metadata: metadata_from_std(&entry.metadata()?),
+            });
+        }
+        Ok(entries)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        std::fs::symlink_metadata(path).map(|m| metadata_from_std(&m))
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        match std::fs::create_dir(path) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+            other => other,
+        }
+    }
+
+    fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+
+    fn write_symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(target, link)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (target, link);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "symlinks are only supported on Unix",
+            ))
+        }
+    }
+
+    fn set_executable(&self, path: &Path, executable: bool) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(path)?.permissions();
+            let mode = perms.mode();
+            perms.set_mode(if executable {
+                mode | 0o111
+            } else {
+                mode & !0o111
+            });
+            std::fs::set_permissions(path, perms)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (path, executable);
+            Ok(())
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+}
+
+/// An in-memory [`Fs`] for hermetic tests.
+#[derive(Default)]
+pub struct FakeFs {
+    entries: Mutex<BTreeMap<PathBuf, Entry>>,
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    File { contents: Vec<u8>, executable: bool },
+    Dir,
+    Symlink(PathBuf),
+}
+
+impl FakeFs {
+    pub fn new() -> Self {
+        let mut entries = BTreeMap::new();
+        entries.insert(PathBuf::from("/"), Entry::Dir);
+        FakeFs {
+            entries: Mutex::new(entries),
+        }
+    }
+
+    fn ensure_parents(entries: &mut BTreeMap<PathBuf, Entry>, path: &Path) {
+        let mut ancestor = path.parent();
+        while let Some(dir) = ancestor {
+            entries.entry(dir.to_path_buf()).or_insert(Entry::Dir);
+            ancestor = dir.parent();
+        }
+    }
+
+    /// Seed a file, creating any missing parent directories.
+    pub fn insert_file(&self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = self.entries.lock().unwrap();
+        Self::ensure_parents(&mut entries, &path);
+        entries.insert(
+            path,
+            Entry::File {
+                contents: contents.into(),
+                executable: false,
+            },
+        );
+    }
+
+    /// Seed an executable file, creating any missing parent directories.
+    pub fn insert_executable(&self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = self.entries.lock().unwrap();
+        Self::ensure_parents(&mut entries, &path);
+        entries.insert(
+            path,
+            Entry::File {
+                contents: contents.into(),
+                executable: true,
+            },
+        );
+    }
+
+    /// Seed a symbolic link, creating any missing parent directories.
+    pub fn insert_symlink(&self, path: impl AsRef<Path>, target: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = self.entries.lock().unwrap();
+        Self::ensure_parents(&mut entries, &path);
+        entries.insert(path, Entry::Symlink(target.as_ref().to_path_buf()));
+    }
+}
+
+fn fake_metadata(entry: &Entry) -> Metadata {
+    match entry {
+        Entry::File {
+            contents,
+            executable,
+        } => Metadata {
+            file_type: FileType::File,
+            len: contents.len() as u64,
+            executable: *executable,
+            modified: None,
+        },
+        Entry::Dir => Metadata {
+            file_type: FileType::Dir,
+            len: 0,
+            executable: false,
+            modified: None,
+        },
+        Entry::Symlink(target) => Metadata {
+            file_type: FileType::Symlink,
+            len: target.as_os_str().len() as u64,
+            executable: false,
+            modified: None,
+        },
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("no such path: {path:?}"))
+}
+
+impl Fs for FakeFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        let entries = self.entries.lock().unwrap();
+        if !matches!(entries.get(path), Some(Entry::Dir)) {
+            return Err(not_found(path));
+        }
+        let mut result = Vec::new();
+        for (child, entry) in entries.iter() {
+            if child.parent() == Some(path) && child != path {
+                result.push(DirEntry {
+                    path: child.clone(),
+                    file_name: child
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    metadata: fake_metadata(entry),
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(path).map(fake_metadata).ok_or_else(|| not_found(path))
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(Entry::File { contents, .. }) => Ok(contents.clone()),
+            Some(Entry::Dir) => Err(io::Error::new(io::ErrorKind::Other, "is a directory")),
+            Some(Entry::Symlink(_)) => {
+                Err(io::Error::new(io::ErrorKind::Other, "is a symlink"))
+            }
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(Entry::Symlink(target)) => Ok(target.clone()),
+            Some(_) => Err(io::Error::new(io::ErrorKind::Other, "not a symlink")),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(path.to_path_buf(), Entry::Dir);
+        Ok(())
+    }
+
+    fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let executable = matches!(
+            entries.get(path),
+            Some(Entry::File { executable: true, .. })
+        );
+        entries.insert(
+            path.to_path_buf(),
+            Entry::File {
+                contents: contents.to_vec(),
+                executable,
+            },
+        );
+        Ok(())
+    }
+
+    fn write_symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(link.to_path_buf(), Entry::Symlink(target.to_path_buf()));
+        Ok(())
+    }
+
+    fn set_executable(&self, path: &Path, executable: bool) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(path) {
+            Some(Entry::File { executable: e, .. }) => {
+                *e = executable;
+                Ok(())
+            }
+            Some(_) => Err(io::Error::new(io::ErrorKind::Other, "not a regular file")),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.remove(from).ok_or_else(|| not_found(from))?;
+        entries.insert(to.to_path_buf(), entry);
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.remove(path).ok_or_else(|| not_found(path))?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_fake_fs_roundtrip() {
+    let fs = FakeFs::new();
+    fs.insert_file("/repo/src/main.rs", b"fn main() {}".to_vec());
+    let entries = fs.read_dir(Path::new("/repo/src")).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].file_name, "main.rs");
+    assert!(entries[0].metadata.is_file());
+    assert_eq!(
+        fs.read_file(Path::new("/repo/src/main.rs")).unwrap(),
+        b"fn main() {}"
+    );
+}
@@ -0,0 +1,151 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// A repository lock, held for as long as the guard is alive.
+///
+/// The lock is the file `.rev/lock`, created atomically with `O_CREAT|O_EXCL`
+/// and containing the holder's PID and hostname. Acquisition never blocks: if
+/// the file already exists the attempt fails immediately with
+/// [`LockError::AlreadyHeld`], unless the recorded holder is a dead process on
+/// this same host, in which case the stale lock is broken and reclaimed. Only
+/// on Linux is the holder's liveness probed; elsewhere any recorded nonzero
+/// PID is taken to be alive, so stale locks are not reclaimed there.
+pub struct Lock {
+    path: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum LockError {
+    /// The lock is held by a live process (or one on another host, whose
+    /// liveness we cannot determine).
+    AlreadyHeld { pid: u32, host: String },
+    IO(std::io::Error),
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::AlreadyHeld { pid, host } => {
+                write!(f, "repository is locked by pid {pid} on {host}")
+            }
+            LockError::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+impl From<std::io::Error> for LockError {
+    fn from(e: std::io::Error) -> Self {
+        LockError::IO(e)
+    }
+}
+
+impl Lock {
+    /// Acquire the repository lock, failing fast if it is already held.
+    pub fn acquire(root: &Path) -> Result<Lock, LockError> {
+        let dir = root.join(".rev");
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+        }
+        let path = dir.join("lock");
+        Self::try_create(&path)?;
+        Ok(Lock { path })
+    }
+
+    /// Try to create the lock file, breaking a stale lock once if the recorded
+    /// holder is a dead process on this host.
+    fn try_create(path: &Path) -> Result<(), LockError> {
+        match Self::create_new(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let (pid, host) = read_holder(path).unwrap_or((0, String::new()));
+                if host == hostname() && !process_alive(pid) {
+                    // The holder crashed; reclaim the lock.
+                    fs::remove_file(path)?;
+                    Self::create_new(path).map_err(LockError::IO)
+                } else {
+                    Err(LockError::AlreadyHeld { pid, host })
+                }
+            }
+            Err(e) => Err(LockError::IO(e)),
+        }
+    }
+
+    fn create_new(path: &Path) -> std::io::Result<()> {
+        // `create_new` maps to `O_CREAT | O_EXCL`, so this is atomic against
+        // other writers racing to take the lock.
+        let mut f = File::options().write(true).create_new(true).open(path)?;
+        write!(f, "{}\n{}\n", std::process::id(), hostname())?;
+        Ok(())
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        // Best-effort release; a failure here cannot be meaningfully handled.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Acquire the repository lock, run `f`, then release the lock.
+pub fn with_lock<T>(root: &Path, f: impl FnOnce() -> T) -> Result<T, LockError> {
+    let _lock = Lock::acquire(root)?;
+    Ok(f())
+}
+
+fn read_holder(path: &Path) -> Option<(u32, String)> {
+    let mut contents = String::new();
+    File::options()
+        .read(true)
+        .open(path)
+        .ok()?
+        .read_to_string(&mut contents)
+        .ok()?;
+    let mut lines = contents.lines();
+    let pid = lines.next()?.trim().parse().ok()?;
+    let host = lines.next().unwrap_or_default().trim().to_string();
+    Some((pid, host))
+}
+
+fn hostname() -> String {
+    if let Ok(host) = std::env::var("HOSTNAME") {
+        if !host.is_empty() {
+            return host;
+        }
+    }
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| String::from("unknown"))
+}
+
+/// Whether a process with the given PID is currently running on this host.
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    pid != 0 && Path::new(&format!("/proc/{pid}")).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_alive(pid: u32) -> bool {
+    // Without a portable liveness probe, assume the holder is alive so we err
+    // on the side of not stealing a live lock.
+    pid != 0
+}
+
+#[test]
+fn test_lock_is_exclusive() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let root = tempdir.path();
+    let lock = Lock::acquire(root).unwrap();
+    match Lock::acquire(root) {
+        Err(LockError::AlreadyHeld { pid, .. }) => assert_eq!(pid, std::process::id()),
+        other => panic!("expected AlreadyHeld, got {other:?}"),
+    }
+    drop(lock);
+    // Releasing the guard frees the lock for the next acquirer.
+    let _lock = Lock::acquire(root).unwrap();
+}